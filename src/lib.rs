@@ -1,3 +1,5 @@
+use std::fmt;
+use std::result::Result;
 use std::sync::Mutex;
 use windows::{Win32::Foundation::*, Win32::Globalization::*, Win32::System::Com::*, core::*};
 
@@ -10,21 +12,79 @@ use windows::{Win32::Foundation::*, Win32::Globalization::*, Win32::System::Com:
 // (though it's OK if this happens multiple times or other libraries do so too)
 static COM_INIT: Mutex<bool> = Mutex::new(false);
 
-fn try_init_com() {
+fn try_init_com() -> Result<(), SpellError> {
     let mut com_init = COM_INIT.lock().unwrap();
     if !*com_init {
+        unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }
+            .ok()
+            .map_err(SpellError::ComInit)?;
         *com_init = true;
-        drop(com_init);
-        unsafe {
-            CoInitializeEx(None, COINIT_MULTITHREADED)
-                .ok()
-                .expect("Failed to initialize COM!");
+    }
+    Ok(())
+}
+
+/// The reason a [Spellchecker] operation failed.
+#[derive(Debug)]
+pub enum SpellError {
+    /// COM could not be initialized.
+    ComInit(windows::core::Error),
+    /// The requested locale has no installed spellchecking engine.
+    LocaleNotSupported,
+    /// A COM call into the Windows spellchecking API failed.
+    Hresult(windows::core::Error),
+    /// A reported UTF-16 offset did not land on a char boundary of the checked text.
+    Utf16Conversion,
+}
+
+impl fmt::Display for SpellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpellError::ComInit(e) => write!(f, "failed to initialize COM: {e}"),
+            SpellError::LocaleNotSupported => {
+                write!(f, "the requested locale has no installed spellchecking engine")
+            }
+            SpellError::Hresult(e) => write!(f, "{e}"),
+            SpellError::Utf16Conversion => {
+                write!(f, "could not map a UTF-16 offset to a UTF-8 byte offset")
+            }
         }
     }
 }
 
+impl std::error::Error for SpellError {}
+
+impl From<windows::core::Error> for SpellError {
+    fn from(e: windows::core::Error) -> Self {
+        SpellError::Hresult(e)
+    }
+}
+
 pub struct Spellchecker(ISpellChecker);
 
+#[implement(ISpellCheckerChangedEventHandler)]
+struct SpellCheckerChangedHandler(Box<dyn Fn() + Send + 'static>);
+
+impl ISpellCheckerChangedEventHandler_Impl for SpellCheckerChangedHandler_Impl {
+    fn Invoke(&self, _sender: Option<&ISpellChecker>) -> windows::core::Result<()> {
+        (self.0)();
+        Ok(())
+    }
+}
+
+/// Cancels an [Spellchecker::on_changed] subscription when dropped.
+pub struct SubscriptionGuard {
+    checker: ISpellChecker,
+    token: i64,
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = self.checker.remove_SpellCheckerChanged(self.token);
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 pub enum Correction {
     #[default]
@@ -34,59 +94,268 @@ pub enum Correction {
     Replacement(String),
 }
 
+/// The default cap on how many suggestions [Spellchecker::check_word] returns, matching the
+/// handful most browsers show in their right-click spelling menu.
+pub const DEFAULT_MAX_SUGGESTIONS: usize = 5;
+
+/// The outcome of checking a single word with [Spellchecker::check_word].
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum WordResult {
+    Correct,
+    Incorrect { suggestions: Vec<String> },
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 pub struct SpellingError {
+    /// Byte offset of the error within the checked `&str`, safe to use for UTF-8 slicing.
     pub start: usize,
+    /// Byte length of the error within the checked `&str`, safe to use for UTF-8 slicing.
     pub length: usize,
+    /// The offset originally reported by the Windows API, in UTF-16 code units.
+    pub utf16_start: usize,
+    /// The length originally reported by the Windows API, in UTF-16 code units.
+    pub utf16_length: usize,
     pub correction: Correction,
 }
 
+/// Converts a `[utf16_start, utf16_start + utf16_length)` span, as reported by the Windows
+/// spellchecking API, into the matching UTF-8 byte range within `text`.
+fn utf16_span_to_byte_range(
+    text: &str,
+    utf16_start: usize,
+    utf16_length: usize,
+) -> Result<(usize, usize), SpellError> {
+    let utf16_end = utf16_start + utf16_length;
+    let mut byte_start = None;
+    let mut byte_end = None;
+    let mut utf16_pos = 0;
+    for (byte_pos, ch) in text.char_indices() {
+        if utf16_pos == utf16_start {
+            byte_start = Some(byte_pos);
+        }
+        if utf16_pos == utf16_end {
+            byte_end = Some(byte_pos);
+        }
+        utf16_pos += ch.len_utf16();
+    }
+    if utf16_pos == utf16_start {
+        byte_start = Some(text.len());
+    }
+    if utf16_pos == utf16_end {
+        byte_end = Some(text.len());
+    }
+    Ok((
+        byte_start.ok_or(SpellError::Utf16Conversion)?,
+        byte_end.ok_or(SpellError::Utf16Conversion)?,
+    ))
+}
+
+#[cfg(test)]
+mod utf16_span_tests {
+    use super::utf16_span_to_byte_range;
+    use crate::SpellError;
+
+    #[test]
+    fn ascii_only() {
+        let text = "hello world";
+        // "world" starts at UTF-16 unit 6, length 5.
+        assert_eq!(utf16_span_to_byte_range(text, 6, 5).unwrap(), (6, 11));
+    }
+
+    #[test]
+    fn multi_byte_non_surrogate_chars() {
+        let text = "café latte";
+        // 'é' is 2 bytes in UTF-8 but 1 UTF-16 code unit, so "latte" (after it) is
+        // still at UTF-16 unit 5 but UTF-8 byte 6.
+        assert_eq!(utf16_span_to_byte_range(text, 5, 5).unwrap(), (6, 11));
+    }
+
+    #[test]
+    fn astral_surrogate_pair_chars() {
+        // U+1F600 "😀" is 4 bytes in UTF-8 but a surrogate pair (2 UTF-16 code units).
+        let text = "😀 dust";
+        // "dust" starts after the emoji (2 UTF-16 units) and the space (1 unit).
+        assert_eq!(utf16_span_to_byte_range(text, 3, 4).unwrap(), (5, 9));
+    }
+
+    #[test]
+    fn span_landing_mid_surrogate_pair_is_an_error() {
+        let text = "😀 dust";
+        // UTF-16 unit 1 is the second half of the emoji's surrogate pair, not a char boundary.
+        assert!(matches!(
+            utf16_span_to_byte_range(text, 1, 1),
+            Err(SpellError::Utf16Conversion)
+        ));
+    }
+
+    #[test]
+    fn zero_length_span_at_end_of_string() {
+        let text = "hello";
+        assert_eq!(utf16_span_to_byte_range(text, 5, 0).unwrap(), (5, 5));
+    }
+}
+
 impl Spellchecker {
-    pub fn new(locale: &str) -> Option<Self> {
-        try_init_com();
+    pub fn new(locale: &str) -> Result<Self, SpellError> {
+        try_init_com()?;
         let factory: ISpellCheckerFactory =
-            unsafe { CoCreateInstance(&SpellCheckerFactory, None, CLSCTX_ALL) }.ok()?;
+            unsafe { CoCreateInstance(&SpellCheckerFactory, None, CLSCTX_ALL) }?;
         let locale = HSTRING::from(locale);
-        let local_supported = unsafe { factory.IsSupported(&locale) }.ok()?;
-        if !local_supported.as_bool() {
-            return None;
+        let locale_supported = unsafe { factory.IsSupported(&locale) }?;
+        if !locale_supported.as_bool() {
+            return Err(SpellError::LocaleNotSupported);
         }
-        let checker = unsafe { factory.CreateSpellChecker(&locale) }.ok()?;
-        Some(Self(checker))
+        let checker = unsafe { factory.CreateSpellChecker(&locale) }?;
+        Ok(Self(checker))
     }
 
-    pub fn new_en() -> Option<Self> {
+    pub fn new_en() -> Result<Self, SpellError> {
         Self::new("en-US")
     }
 
-    pub fn check(&self, text: &str) -> Option<Vec<SpellingError>> {
-        let errors = unsafe { self.0.ComprehensiveCheck(&HSTRING::from(text)) }.ok()?;
+    /// Tries each locale in `preferred`, in order, and returns a checker for the first one
+    /// supported by the OS. Useful for populating a language dropdown with a sane default,
+    /// e.g. `Spellchecker::new_with_fallback(&["en-GB", "en-US", "en"])`.
+    pub fn new_with_fallback(preferred: &[&str]) -> Result<Self, SpellError> {
+        let mut last_err = SpellError::LocaleNotSupported;
+        for locale in preferred {
+            match Self::new(locale) {
+                Ok(checker) => return Ok(checker),
+                Err(e) => last_err = e,
+            }
+        }
+        Err(last_err)
+    }
+
+    /// Lists every locale tag the installed Windows spellchecking engines support,
+    /// e.g. `"en-US"`, `"fr-FR"`.
+    pub fn supported_locales() -> Result<Vec<String>, SpellError> {
+        try_init_com()?;
+        let factory: ISpellCheckerFactory =
+            unsafe { CoCreateInstance(&SpellCheckerFactory, None, CLSCTX_ALL) }?;
+        let languages = unsafe { factory.SupportedLanguages() }?;
+        let mut results = Vec::new();
+        let mut language = [PWSTR::null()];
+        while unsafe { languages.Next(&mut language, None) } == S_OK && !language[0].is_null() {
+            results.push(unsafe { language[0].to_string() }?);
+            unsafe { CoTaskMemFree(Some(language[0].as_ptr() as *mut _)) };
+        }
+        Ok(results)
+    }
+
+    /// Tells the spellchecker to stop flagging `word` for the remainder of this session,
+    /// without adding it to the user's dictionary.
+    pub fn ignore(&self, word: &str) -> Result<(), SpellError> {
+        unsafe { self.0.Ignore(&HSTRING::from(word)) }?;
+        Ok(())
+    }
+
+    /// Adds `word` to the user's custom dictionary, so it is no longer flagged across sessions.
+    pub fn add(&self, word: &str) -> Result<(), SpellError> {
+        unsafe { self.0.Add(&HSTRING::from(word)) }?;
+        Ok(())
+    }
+
+    /// Registers an autocorrect rule that replaces `from` with `to`.
+    pub fn auto_correct(&self, from: &str, to: &str) -> Result<(), SpellError> {
+        unsafe {
+            self.0
+                .AutoCorrect(&HSTRING::from(from), &HSTRING::from(to))
+        }?;
+        Ok(())
+    }
+
+    /// Removes a previously added or ignored `word`, so it is flagged again.
+    ///
+    /// `Remove` lives on `ISpellChecker2` rather than `ISpellChecker`, so this casts to the
+    /// extended interface first.
+    pub fn remove(&self, word: &str) -> Result<(), SpellError> {
+        let checker2: ISpellChecker2 = self.0.cast()?;
+        unsafe { checker2.Remove(&HSTRING::from(word)) }?;
+        Ok(())
+    }
+
+    /// Subscribes `callback` to run whenever the user dictionary changes, e.g. after
+    /// [Spellchecker::add]/[Spellchecker::remove] or an OS-level dictionary update, so callers
+    /// can re-run [Spellchecker::check] on visible text instead of polling. The subscription is
+    /// cancelled when the returned [SubscriptionGuard] is dropped.
+    pub fn on_changed(
+        &self,
+        callback: impl Fn() + Send + 'static,
+    ) -> Result<SubscriptionGuard, SpellError> {
+        let handler: ISpellCheckerChangedEventHandler =
+            SpellCheckerChangedHandler(Box::new(callback)).into();
+        let token = unsafe { self.0.add_SpellCheckerChanged(&handler) }?;
+        Ok(SubscriptionGuard {
+            checker: self.0.clone(),
+            token,
+        })
+    }
+
+    /// Checks a single `word`, returning at most [DEFAULT_MAX_SUGGESTIONS] suggestions if it's
+    /// misspelled. Use [Spellchecker::check_word_capped] to set a different cap.
+    pub fn check_word(&self, word: &str) -> Result<WordResult, SpellError> {
+        self.check_word_capped(word, DEFAULT_MAX_SUGGESTIONS)
+    }
+
+    /// Checks a single `word`, collecting at most `max_suggestions` suggestions if it's
+    /// misspelled. Built on `ISpellChecker::Check` and `Suggest` directly (rather than
+    /// `ComprehensiveCheck`) and stops draining the suggestion enumerator once the cap is hit.
+    pub fn check_word_capped(
+        &self,
+        word: &str,
+        max_suggestions: usize,
+    ) -> Result<WordResult, SpellError> {
+        let errors = unsafe { self.0.Check(&HSTRING::from(word)) }?;
+        let mut err = None;
+        let is_misspelled = unsafe { errors.Next(&mut err) } == S_OK && err.is_some();
+        if !is_misspelled {
+            return Ok(WordResult::Correct);
+        }
+
+        let suggestions_enum = unsafe { self.0.Suggest(&HSTRING::from(word)) }?;
+        let mut suggestions = Vec::new();
+        let mut suggestion = [PWSTR::null()];
+        while suggestions.len() < max_suggestions
+            && unsafe { suggestions_enum.Next(&mut suggestion, None) } == S_OK
+            && !suggestion[0].is_null()
+        {
+            suggestions.push(unsafe { suggestion[0].to_string() }?);
+            unsafe { CoTaskMemFree(Some(suggestion[0].as_ptr() as *mut _)) };
+        }
+        Ok(WordResult::Incorrect { suggestions })
+    }
+
+    pub fn check(&self, text: &str) -> Result<Vec<SpellingError>, SpellError> {
+        let errors = unsafe { self.0.ComprehensiveCheck(&HSTRING::from(text)) }?;
         let mut err = None;
         let mut results = Vec::new();
         while unsafe { errors.Next(&mut err) } == S_OK {
             let err = err.take().unwrap();
-            let start = unsafe { err.StartIndex() }.ok()? as usize;
-            let length = unsafe { err.Length() }.ok()? as usize;
-            let correction = unsafe { err.CorrectiveAction() }.ok()?;
+            let utf16_start = unsafe { err.StartIndex() }? as usize;
+            let utf16_length = unsafe { err.Length() }? as usize;
+            let (start, end) = utf16_span_to_byte_range(text, utf16_start, utf16_length)?;
+            let length = end - start;
+            let correction = unsafe { err.CorrectiveAction() }?;
             let correction: Correction = match correction {
                 CORRECTIVE_ACTION_DELETE => Correction::Delete,
                 CORRECTIVE_ACTION_GET_SUGGESTIONS => {
                     let mut results = Vec::new();
-                    let substring = &text[start..(start + length)];
-                    let suggestions = unsafe { self.0.Suggest(&HSTRING::from(substring)) }.ok()?;
+                    let substring = &text[start..end];
+                    let suggestions = unsafe { self.0.Suggest(&HSTRING::from(substring)) }?;
                     let mut suggestion = [PWSTR::null()];
                     while unsafe { suggestions.Next(&mut suggestion, None) } == S_OK
                         && !suggestion[0].is_null()
                     {
-                        results.push(unsafe { suggestion[0].to_string() }.ok()?);
+                        results.push(unsafe { suggestion[0].to_string() }?);
                         unsafe { CoTaskMemFree(Some(suggestion[0].as_ptr() as *mut _)) };
                     }
 
                     Correction::Suggestions(results)
                 }
                 CORRECTIVE_ACTION_REPLACE => {
-                    let replacement = unsafe { err.Replacement() }.ok()?;
-                    let replacement_s = unsafe { replacement.to_string() }.ok()?;
+                    let replacement = unsafe { err.Replacement() }?;
+                    let replacement_s = unsafe { replacement.to_string() }?;
                     unsafe { CoTaskMemFree(Some(replacement.as_ptr() as *mut _)) };
                     Correction::Replacement(replacement_s)
                 }
@@ -95,16 +364,140 @@ impl Spellchecker {
             results.push(SpellingError {
                 start,
                 length,
+                utf16_start,
+                utf16_length,
                 correction,
             });
         }
-        Some(results)
+        Ok(results)
+    }
+}
+
+/// An order-preserving, case-insensitive-deduplicated list of suggestions, built up in
+/// checker-priority order. Mirrors how LibreOffice merges suggestions from several
+/// dictionaries into one proposal list.
+#[derive(Clone, Debug, Default)]
+struct ProposalList(Vec<String>);
+
+impl ProposalList {
+    fn has_entry(&self, word: &str) -> bool {
+        self.0.iter().any(|entry| entry.to_lowercase() == word.to_lowercase())
+    }
+
+    fn push(&mut self, word: String) {
+        if !self.has_entry(&word) {
+            self.0.push(word);
+        }
+    }
+
+    fn into_vec(self) -> Vec<String> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod proposal_list_tests {
+    use super::ProposalList;
+
+    #[test]
+    fn dedups_case_insensitively_and_preserves_order() {
+        let mut proposals = ProposalList::default();
+        proposals.push("Foo".to_string());
+        proposals.push("bar".to_string());
+        proposals.push("foo".to_string());
+        assert_eq!(proposals.into_vec(), vec!["Foo".to_string(), "bar".to_string()]);
+    }
+}
+
+/// Checks text or words against several locales at once, e.g. for multilingual documents.
+/// A word is only considered misspelled if every checker flags it, and suggestions from all
+/// checkers are merged, in checker-priority order, with case-insensitive duplicates dropped.
+pub struct MultiSpellchecker(Vec<Spellchecker>);
+
+impl MultiSpellchecker {
+    pub fn new(checkers: Vec<Spellchecker>) -> Self {
+        Self(checkers)
+    }
+
+    /// Checks `word` against every locale, merging suggestions from checkers that flag it.
+    /// With no checkers configured, there is nothing to flag the word, so it is reported correct.
+    pub fn check_word(&self, word: &str, max_suggestions: usize) -> Result<WordResult, SpellError> {
+        if self.0.is_empty() {
+            return Ok(WordResult::Correct);
+        }
+        let mut proposals = ProposalList::default();
+        let mut all_incorrect = true;
+        for checker in &self.0 {
+            match checker.check_word_capped(word, max_suggestions)? {
+                WordResult::Correct => all_incorrect = false,
+                WordResult::Incorrect { suggestions } => {
+                    for suggestion in suggestions {
+                        proposals.push(suggestion);
+                    }
+                }
+            }
+        }
+        if all_incorrect {
+            Ok(WordResult::Incorrect {
+                suggestions: proposals.into_vec(),
+            })
+        } else {
+            Ok(WordResult::Correct)
+        }
+    }
+
+    /// Checks `text` against every locale, mirroring [Spellchecker::check]. A region is only
+    /// reported as an error if every checker flags that same `[start, start + length)` span,
+    /// and suggestions from all checkers that flagged it are merged through a [ProposalList].
+    /// Each reported error keeps the first checker's [Correction]; the merged suggestions are
+    /// used only when that correction is [Correction::Suggestions], and are dropped otherwise.
+    pub fn check(&self, text: &str) -> Result<Vec<SpellingError>, SpellError> {
+        if self.0.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut per_checker = Vec::with_capacity(self.0.len());
+        for checker in &self.0 {
+            per_checker.push(checker.check(text)?);
+        }
+
+        let mut results = Vec::new();
+        for candidate in &per_checker[0] {
+            let mut proposals = ProposalList::default();
+            let mut flagged_by_all = true;
+            for errors in &per_checker {
+                let matching = errors
+                    .iter()
+                    .find(|e| e.start == candidate.start && e.length == candidate.length);
+                match matching {
+                    Some(error) => {
+                        if let Correction::Suggestions(suggestions) = &error.correction {
+                            for suggestion in suggestions.iter().cloned() {
+                                proposals.push(suggestion);
+                            }
+                        }
+                    }
+                    None => {
+                        flagged_by_all = false;
+                        break;
+                    }
+                }
+            }
+            if !flagged_by_all {
+                continue;
+            }
+            let mut merged = candidate.clone();
+            if let Correction::Suggestions(_) = &merged.correction {
+                merged.correction = Correction::Suggestions(proposals.into_vec());
+            }
+            results.push(merged);
+        }
+        Ok(results)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Spellchecker;
+    use crate::{Spellchecker, WordResult};
 
     #[test]
     fn test() {
@@ -117,4 +510,36 @@ mod tests {
         let errors2 = spellchecker2.check(text).unwrap();
         assert_eq!(errors, errors2);
     }
+
+    #[test]
+    fn user_dictionary_round_trip() {
+        let spellchecker = Spellchecker::new_en().expect("Failed to create english spellchecker!");
+        spellchecker
+            .add("flibbertigibbet")
+            .expect("add should succeed");
+        spellchecker
+            .ignore("whitness")
+            .expect("ignore should succeed");
+        spellchecker
+            .auto_correct("teh", "the")
+            .expect("auto_correct should succeed");
+        spellchecker
+            .remove("flibbertigibbet")
+            .expect("remove should succeed");
+    }
+
+    #[test]
+    fn check_word_reports_correct_and_capped_suggestions() {
+        let spellchecker = Spellchecker::new_en().expect("Failed to create english spellchecker!");
+
+        assert_eq!(
+            spellchecker.check_word("another").unwrap(),
+            WordResult::Correct
+        );
+
+        match spellchecker.check_word_capped("whitness", 2).unwrap() {
+            WordResult::Incorrect { suggestions } => assert!(suggestions.len() <= 2),
+            WordResult::Correct => panic!("expected \"whitness\" to be flagged as misspelled"),
+        }
+    }
 }